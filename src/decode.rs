@@ -0,0 +1,163 @@
+use crate::json::JSONValue;
+
+use std::collections::HashMap;
+
+/// One step on the path from the decoded value's root to a mismatch
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Describes a type mismatch encountered while decoding a `JSONValue` into a
+/// native Rust type, including the path to the offending value
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeError {
+    pub expected: &'static str,
+    pub found: &'static str,
+    pub path: Vec<PathSegment>,
+}
+
+impl DecodeError {
+    fn new(expected: &'static str, found: &JSONValue) -> DecodeError {
+        DecodeError {
+            expected,
+            found: type_name(found),
+            path: Vec::new(),
+        }
+    }
+
+    /// Records an outer container's key/index as the error bubbles up
+    fn with_prefix(mut self, segment: PathSegment) -> DecodeError {
+        self.path.insert(0, segment);
+        self
+    }
+}
+
+fn type_name(value: &JSONValue) -> &'static str {
+    match value {
+        JSONValue::Object(_) => "object",
+        JSONValue::Array(_) => "array",
+        JSONValue::Bool(_) => "bool",
+        JSONValue::Text(_) => "string",
+        JSONValue::Number(_) => "number",
+        JSONValue::Null => "null",
+    }
+}
+
+/// Types that can be decoded from a `JSONValue`
+pub trait FromJson: Sized {
+    fn from_json(value: &JSONValue) -> Result<Self, DecodeError>;
+}
+
+impl FromJson for bool {
+    fn from_json(value: &JSONValue) -> Result<Self, DecodeError> {
+        match value {
+            JSONValue::Bool(b) => Ok(*b),
+            other => Err(DecodeError::new("bool", other)),
+        }
+    }
+}
+
+impl FromJson for f64 {
+    fn from_json(value: &JSONValue) -> Result<Self, DecodeError> {
+        match value {
+            JSONValue::Number(n) => Ok(*n),
+            other => Err(DecodeError::new("number", other)),
+        }
+    }
+}
+
+impl FromJson for i64 {
+    fn from_json(value: &JSONValue) -> Result<Self, DecodeError> {
+        match value {
+            JSONValue::Number(n) => Ok(*n as i64),
+            other => Err(DecodeError::new("number", other)),
+        }
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &JSONValue) -> Result<Self, DecodeError> {
+        match value {
+            JSONValue::Text(s) => Ok(s.clone()),
+            other => Err(DecodeError::new("string", other)),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &JSONValue) -> Result<Self, DecodeError> {
+        match value {
+            JSONValue::Array(vals) => vals
+                .iter()
+                .enumerate()
+                .map(|(i, v)| T::from_json(v).map_err(|e| e.with_prefix(PathSegment::Index(i))))
+                .collect(),
+            other => Err(DecodeError::new("array", other)),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &JSONValue) -> Result<Self, DecodeError> {
+        match value {
+            JSONValue::Null => Ok(None),
+            other => T::from_json(other).map(Some),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for HashMap<String, T> {
+    fn from_json(value: &JSONValue) -> Result<Self, DecodeError> {
+        match value {
+            JSONValue::Object(map) => map
+                .iter()
+                .map(|(k, v)| {
+                    T::from_json(v)
+                        .map(|val| (k.clone(), val))
+                        .map_err(|e| e.with_prefix(PathSegment::Key(k.clone())))
+                })
+                .collect(),
+            other => Err(DecodeError::new("object", other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_primitives() {
+        assert_eq!(bool::from_json(&JSONValue::Bool(true)), Ok(true));
+        assert_eq!(f64::from_json(&JSONValue::Number(3.14)), Ok(3.14));
+        assert_eq!(String::from_json(&"hi".into()), Ok("hi".to_owned()));
+    }
+
+    #[test]
+    fn decode_type_mismatch() {
+        let err = bool::from_json(&JSONValue::Number(1.0)).unwrap_err();
+
+        assert_eq!(err.expected, "bool");
+        assert_eq!(err.found, "number");
+    }
+
+    #[test]
+    fn decode_vec_reports_index_path() {
+        let value: JSONValue = vec![true.into(), JSONValue::Number(1.0)].into();
+
+        let err = Vec::<bool>::from_json(&value).unwrap_err();
+
+        assert_eq!(err.path, vec![PathSegment::Index(1)]);
+    }
+
+    #[test]
+    fn decode_option_null() {
+        assert_eq!(Option::<bool>::from_json(&JSONValue::Null), Ok(None));
+        assert_eq!(
+            Option::<bool>::from_json(&JSONValue::Bool(false)),
+            Ok(Some(false))
+        );
+    }
+}
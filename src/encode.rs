@@ -0,0 +1,184 @@
+use crate::json::JSONValue;
+
+/// Serializes a JSONValue into a compact JSON string
+pub fn to_string(value: &JSONValue) -> String {
+    let mut out = String::new();
+
+    encode_value(value, &mut out, None, 0);
+
+    out
+}
+
+/// Serializes a JSONValue into a pretty-printed JSON string, indenting nested
+/// objects/arrays by `indent` spaces per level
+pub fn to_string_pretty(value: &JSONValue, indent: usize) -> String {
+    let mut out = String::new();
+
+    encode_value(value, &mut out, Some(indent), 0);
+
+    out
+}
+
+fn newline_and_indent(out: &mut String, indent: Option<usize>, depth: usize) {
+    if let Some(indent) = indent {
+        out.push('\n');
+        out.extend(std::iter::repeat(' ').take(indent * depth));
+    }
+}
+
+fn encode_value(value: &JSONValue, out: &mut String, indent: Option<usize>, depth: usize) {
+    match value {
+        JSONValue::Object(map) => encode_object(map, out, indent, depth),
+        JSONValue::Array(vals) => encode_array(vals, out, indent, depth),
+        JSONValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        JSONValue::Text(s) => encode_string(s, out),
+        JSONValue::Number(n) => out.push_str(&encode_number(*n)),
+        JSONValue::Null => out.push_str("null"),
+    }
+}
+
+/// Encodes a number, falling back to the `NaN`/`Infinity`/`-Infinity`
+/// literals (recognized on the way back in by `ParseOptions::allow_nan_and_infinity`)
+/// for non-finite values, since RFC 8259 has no representation for them
+fn encode_number(n: f64) -> String {
+    if n.is_nan() {
+        "NaN".to_owned()
+    } else if n.is_infinite() {
+        if n.is_sign_negative() {
+            "-Infinity".to_owned()
+        } else {
+            "Infinity".to_owned()
+        }
+    } else {
+        n.to_string()
+    }
+}
+
+fn encode_object(
+    map: &std::collections::HashMap<String, JSONValue>,
+    out: &mut String,
+    indent: Option<usize>,
+    depth: usize,
+) {
+    out.push('{');
+
+    let mut first = true;
+
+    for (key, val) in map {
+        if !first {
+            out.push(',');
+        }
+        first = false;
+
+        newline_and_indent(out, indent, depth + 1);
+
+        encode_string(key, out);
+        out.push(':');
+        if indent.is_some() {
+            out.push(' ');
+        }
+
+        encode_value(val, out, indent, depth + 1);
+    }
+
+    if !map.is_empty() {
+        newline_and_indent(out, indent, depth);
+    }
+
+    out.push('}');
+}
+
+fn encode_array(vals: &[JSONValue], out: &mut String, indent: Option<usize>, depth: usize) {
+    out.push('[');
+
+    let mut first = true;
+
+    for val in vals {
+        if !first {
+            out.push(',');
+        }
+        first = false;
+
+        newline_and_indent(out, indent, depth + 1);
+
+        encode_value(val, out, indent, depth + 1);
+    }
+
+    if !vals.is_empty() {
+        newline_and_indent(out, indent, depth);
+    }
+
+    out.push(']');
+}
+
+/// Quotes and escapes a string per RFC 8259
+fn encode_string(s: &str, out: &mut String) {
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                out.push_str(&format!("\\u{:04x}", c as u32));
+            }
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::JSONValue;
+    use std::collections::HashMap;
+
+    #[test]
+    fn encode_primitives() {
+        assert_eq!(to_string(&JSONValue::Null), "null");
+        assert_eq!(to_string(&JSONValue::Bool(true)), "true");
+        assert_eq!(to_string(&JSONValue::Number(3.14)), "3.14");
+        assert_eq!(to_string(&"hi".into()), "\"hi\"");
+    }
+
+    #[test]
+    fn encode_escapes_control_chars() {
+        let value: JSONValue = "a\n\t\"\\b".into();
+
+        assert_eq!(to_string(&value), "\"a\\n\\t\\\"\\\\b\"");
+    }
+
+    #[test]
+    fn encode_non_finite_numbers() {
+        assert_eq!(to_string(&JSONValue::Number(f64::NAN)), "NaN");
+        assert_eq!(to_string(&JSONValue::Number(f64::INFINITY)), "Infinity");
+        assert_eq!(
+            to_string(&JSONValue::Number(f64::NEG_INFINITY)),
+            "-Infinity"
+        );
+    }
+
+    #[test]
+    fn encode_array() {
+        let value: JSONValue = vec![1.0.into(), "two".into(), JSONValue::Null].into();
+
+        assert_eq!(to_string(&value), "[1,\"two\",null]");
+    }
+
+    #[test]
+    fn encode_object_pretty() {
+        let mut map = HashMap::<&str, JSONValue>::new();
+        map.insert("a", true.into());
+
+        let value: JSONValue = map.into();
+
+        assert_eq!(to_string_pretty(&value, 2), "{\n  \"a\": true\n}");
+    }
+}
@@ -1,5 +1,8 @@
+use crate::decode::{DecodeError, FromJson};
+use crate::encode;
 use crate::parse;
 use std::convert::From;
+use std::fmt;
 
 use std::collections::HashMap;
 
@@ -10,6 +13,11 @@ pub fn from_str(text: &str) -> Result<JSONValue, parse::Error> {
     parse_context.parse()
 }
 
+/// Serialize a JSONValue back into a JSON string
+pub fn to_string(value: &JSONValue) -> String {
+    encode::to_string(value)
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum JSONValue {
     Object(HashMap<String, JSONValue>),
@@ -20,6 +28,30 @@ pub enum JSONValue {
     Null,
 }
 
+impl JSONValue {
+    /// Encode this value as a compact JSON string
+    pub fn encode(&self) -> String {
+        encode::to_string(self)
+    }
+
+    /// Encode this value as a pretty-printed JSON string, indenting nested
+    /// objects/arrays by `indent` spaces per level
+    pub fn encode_pretty(&self, indent: usize) -> String {
+        encode::to_string_pretty(self, indent)
+    }
+
+    /// Decode this value into a native Rust type implementing `FromJson`
+    pub fn decode<T: FromJson>(&self) -> Result<T, DecodeError> {
+        T::from_json(self)
+    }
+}
+
+impl fmt::Display for JSONValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", encode::to_string(self))
+    }
+}
+
 impl From<HashMap<String, JSONValue>> for JSONValue {
     fn from(item: HashMap<String, JSONValue>) -> Self {
         JSONValue::Object(item)
@@ -0,0 +1,86 @@
+//! Low-level byte-scanning helpers shared by `ParseContext` (tree builder)
+//! and `StreamParser` (event stream), so a fix to `\u` escape/number lexing only
+//! has to be made once.
+
+use std::str;
+
+use lexical_core;
+
+/// Reads exactly 4 hex digits at `*index`, advancing it by 4 on success
+pub(crate) fn eat_hex4(bytes: &[u8], index: &mut usize) -> Result<u16, ()> {
+    let end = *index + 4;
+
+    if end > bytes.len() {
+        return Err(());
+    }
+
+    let digits = &bytes[*index..end];
+
+    // from_str_radix alone would also accept a leading '+'
+    if !digits.iter().all(u8::is_ascii_hexdigit) {
+        return Err(());
+    }
+
+    let hex_str = unsafe { str::from_utf8_unchecked(digits) };
+
+    match u16::from_str_radix(hex_str, 16) {
+        Ok(code_unit) => {
+            *index = end;
+            Ok(code_unit)
+        }
+        Err(_) => Err(()),
+    }
+}
+
+/// Combines a `\u` escape's code unit into a `char`, consuming a trailing
+/// `\uXXXX` low surrogate escape from `bytes` at `*index` when `code_unit`
+/// is a high surrogate
+pub(crate) fn combine_unicode_escape(
+    bytes: &[u8],
+    index: &mut usize,
+    code_unit: u16,
+) -> Result<char, ()> {
+    let code_point = if (0xD800..=0xDBFF).contains(&code_unit) {
+        // high surrogate, must be followed by a low surrogate escape
+        if bytes.get(*index) != Some(&b'\\') || bytes.get(*index + 1) != Some(&b'u') {
+            return Err(());
+        }
+        *index += 2;
+
+        let low = eat_hex4(bytes, index)?;
+
+        if !(0xDC00..=0xDFFF).contains(&low) {
+            return Err(());
+        }
+
+        0x10000u32 + ((u32::from(code_unit) - 0xD800) << 10) + (u32::from(low) - 0xDC00)
+    } else if (0xDC00..=0xDFFF).contains(&code_unit) {
+        // unpaired low surrogate
+        return Err(());
+    } else {
+        u32::from(code_unit)
+    };
+
+    char::from_u32(code_point).ok_or(())
+}
+
+/// Scans a JSON number literal starting at `*index`, advancing past it and
+/// parsing it into an `f64`
+pub(crate) fn eat_number(bytes: &[u8], index: &mut usize) -> Result<f64, ()> {
+    let idx_start = *index;
+
+    while let Some(&b) = bytes.get(*index) {
+        match b {
+            b'0'...b'9' | b'-' | b'+' | b'.' | b'e' | b'E' => *index += 1,
+            _ => break,
+        }
+    }
+
+    let res = lexical_core::try_atof64_slice(&bytes[idx_start..*index]);
+
+    if res.error.code == lexical_core::ErrorCode::Success {
+        Ok(res.value)
+    } else {
+        Err(())
+    }
+}
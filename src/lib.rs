@@ -0,0 +1,8 @@
+//! Cibola is a JSON parsing and serialization library.
+
+pub mod decode;
+pub mod encode;
+pub mod json;
+mod lex;
+pub mod parse;
+pub mod stream;
@@ -1,11 +1,10 @@
 use crate::json::JSONValue;
+use crate::lex;
 
 use std::collections::HashMap;
 use std::fmt;
 use std::str;
 
-use lexical_core;
-
 #[derive(Debug)]
 pub struct ParseContext<'a> {
     bytes: &'a [u8],
@@ -14,6 +13,32 @@ pub struct ParseContext<'a> {
     buffer: Vec<u8>,
     // index in byte sequence _bytes_
     index: usize,
+    options: ParseOptions,
+}
+
+/// Controls which non-strict JSON5-ish extensions `ParseContext` accepts.
+/// Defaults to strict RFC-8259.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParseOptions {
+    /// Allow a comma directly before a closing `}`/`]`
+    pub allow_trailing_commas: bool,
+    /// Allow a comma directly after an opening `{`/`[`, before the first field/value
+    pub allow_leading_commas: bool,
+    /// Allow `//` and `/* */` comments between tokens
+    pub allow_comments: bool,
+    /// Allow the `NaN`, `Infinity` and `-Infinity` literals as numbers
+    pub allow_nan_and_infinity: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            allow_trailing_commas: false,
+            allow_leading_commas: false,
+            allow_comments: false,
+            allow_nan_and_infinity: false,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -29,7 +54,13 @@ pub enum Error {
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "")
+        match self {
+            Error::EndOfStream => write!(f, "unexpected end of stream"),
+            Error::UnexpectedCharacter { line, col, token } => {
+                write!(f, "unexpected '{}' at line {}, column {}", token, line, col)
+            }
+            Error::InvalidJSON => write!(f, "invalid JSON: document must start with an object or array"),
+        }
     }
 }
 
@@ -37,11 +68,16 @@ type Result<T> = std::result::Result<T, Error>;
 
 impl<'a> ParseContext<'a> {
     pub fn new(text: &'a str) -> ParseContext {
+        ParseContext::new_with_options(text, ParseOptions::default())
+    }
+
+    pub fn new_with_options(text: &'a str, options: ParseOptions) -> ParseContext {
         ParseContext {
             bytes: text.as_bytes(),
             text,
             buffer: Vec::with_capacity(100),
             index: 0,
+            options,
         }
     }
 
@@ -50,26 +86,43 @@ impl<'a> ParseContext<'a> {
         match self.value() {
             o @ Ok(JSONValue::Object(_)) => o,
             a @ Ok(JSONValue::Array(_)) => a,
-            _ => Err(Error::InvalidJSON),
+            Ok(_) => Err(Error::InvalidJSON),
+            Err(e) => Err(e),
         }
     }
 
     fn fail<T>(&self) -> Result<T> {
-        #[cfg(test)]
-        {
-            let s = unsafe { str::from_utf8_unchecked(&self.bytes[0..self.index]) };
-            println!("{}", s);
+        let (line, col) = self.position();
+        let token = self.current_char();
+
+        Err(Error::UnexpectedCharacter { line, col, token })
+    }
+
+    /// Computes the 1-based (line, column) of the current parse position
+    fn position(&self) -> (usize, usize) {
+        let mut line = 1;
+        let mut col = 1;
+
+        for &b in &self.bytes[0..self.index.min(self.bytes.len())] {
+            if b == b'\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
         }
 
-        // TODO: calculate (newline, char) distance to err byte
-        let line = 0;
-        let col = 0;
+        (line, col)
+    }
 
-        Err(Error::UnexpectedCharacter {
-            line,
-            col,
-            token: ' ',
-        })
+    /// Decodes the byte at the current parse position into a `char`,
+    /// falling back to the replacement character at end-of-stream or on an
+    /// invalid UTF-8 boundary
+    fn current_char(&self) -> char {
+        str::from_utf8(&self.bytes[self.index..])
+            .ok()
+            .and_then(|s| s.chars().next())
+            .unwrap_or('\u{FFFD}')
     }
 
     /// Returns byte at index or EOS
@@ -81,16 +134,50 @@ impl<'a> ParseContext<'a> {
         }
     }
 
-    /// Skips '\n', '\r', '\t', ' '
+    /// Skips '\n', '\r', '\t', ' ', and (when enabled) `//` / `/* */` comments
     fn skip_control_chars(&mut self) {
         while let Ok(byte) = self.current_byte() {
             match byte {
                 b'\n' | b'\r' | b'\t' | b' ' => self.accept(),
+                b'/' if self.options.allow_comments && self.skip_comment() => continue,
                 _ => break,
             }
         }
     }
 
+    /// Skips a single `//` or `/* */` comment at the current position, if
+    /// one is present. Assumes the current byte is `/`.
+    fn skip_comment(&mut self) -> bool {
+        match self.bytes.get(self.index + 1) {
+            Some(b'/') => {
+                self.accept_n(2);
+
+                while let Ok(byte) = self.current_byte() {
+                    if byte == b'\n' {
+                        break;
+                    }
+                    self.accept();
+                }
+
+                true
+            }
+            Some(b'*') => {
+                self.accept_n(2);
+
+                while self.index + 1 < self.bytes.len() {
+                    if self.bytes[self.index] == b'*' && self.bytes[self.index + 1] == b'/' {
+                        self.accept_n(2);
+                        break;
+                    }
+                    self.accept();
+                }
+
+                true
+            }
+            _ => false,
+        }
+    }
+
     fn accept(&mut self) {
         self.index += 1;
     }
@@ -99,9 +186,49 @@ impl<'a> ParseContext<'a> {
         self.index += n;
     }
 
-    fn skip_comma(&mut self) {
+    /// Consumes a trailing comma if present. Fails if the comma is directly
+    /// followed by a closing `}`/`]` and `allow_trailing_commas` is not set.
+    fn skip_comma(&mut self) -> Result<()> {
         if let Ok(b',') = self.current_byte() {
             self.accept();
+
+            if !self.options.allow_trailing_commas {
+                self.skip_control_chars();
+
+                if let Ok(b'}') | Ok(b']') = self.current_byte() {
+                    return self.fail();
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Consumes a leading comma directly after an opening `{`/`[`, if present
+    /// and `allow_leading_commas` is set
+    fn skip_leading_comma(&mut self) {
+        if self.options.allow_leading_commas {
+            self.skip_control_chars();
+
+            if let Ok(b',') = self.current_byte() {
+                self.accept();
+                self.skip_control_chars();
+            }
+        }
+    }
+
+    /// Consumes the exact text of `literal` if it matches at the current
+    /// position, without failing (and without bounds-checking panics) if it
+    /// doesn't
+    fn try_eat_literal(&mut self, literal: &'static str) -> bool {
+        let literal_bytes = literal.as_bytes();
+        let end = self.index + literal_bytes.len();
+
+        if end <= self.bytes.len() && &self.bytes[self.index..end] == literal_bytes {
+            self.index = end;
+            true
+        } else {
+            false
         }
     }
 
@@ -174,8 +301,7 @@ impl<'a> ParseContext<'a> {
                 self.accept();
                 let following_b = self.current_byte()?;
 
-                // escape '"' '\' '/' 'b' 'f' 'n' 'r' 't'
-                // TODO: unicode: 'u' hex hex hex hex
+                // escape '"' '\' '/' 'b' 'f' 'n' 'r' 't' 'u' hex hex hex hex
                 match following_b {
                     b'"' => self.buffer.push(b'\"'),
                     b'\\' => self.buffer.push(b'\\'),
@@ -185,6 +311,14 @@ impl<'a> ParseContext<'a> {
                     b'n' => self.buffer.push(b'\n'),
                     b'r' => self.buffer.push(b'\r'),
                     b't' => self.buffer.push(b'\t'),
+                    b'u' => {
+                        self.accept();
+                        let ch = self.eat_unicode_escape()?;
+                        let mut utf8_buf = [0u8; 4];
+                        self.buffer
+                            .extend_from_slice(ch.encode_utf8(&mut utf8_buf).as_bytes());
+                        continue;
+                    }
                     // unexpected byte following escape
                     _ => return self.fail(),
                 }
@@ -201,6 +335,19 @@ impl<'a> ParseContext<'a> {
         Ok(buffered_str)
     }
 
+    /// Consumes exactly 4 hex digits, e.g. the `00e9` of the escape for `é`
+    fn eat_hex4(&mut self) -> Result<u16> {
+        lex::eat_hex4(self.bytes, &mut self.index).or_else(|_| self.fail())
+    }
+
+    /// Consumes a `\u` escape's 4 hex digits (the leading `\u` is already
+    /// consumed), combining a high/low surrogate pair into a single `char`
+    fn eat_unicode_escape(&mut self) -> Result<char> {
+        let code_unit = self.eat_hex4()?;
+
+        lex::combine_unicode_escape(self.bytes, &mut self.index, code_unit).or_else(|_| self.fail())
+    }
+
     /// Consumes an object
     fn object(&mut self) -> Result<JSONValue> {
         self.skip_control_chars();
@@ -216,6 +363,8 @@ impl<'a> ParseContext<'a> {
 
     /// Consumes object fields
     fn object_fields(&mut self) -> Result<HashMap<String, JSONValue>> {
+        self.skip_leading_comma();
+
         let b = self.current_byte()?;
         let mut hashmap = HashMap::<String, JSONValue>::new();
 
@@ -272,6 +421,8 @@ impl<'a> ParseContext<'a> {
 
     /// Consumes comma separated values
     fn array_values(&mut self) -> Result<Vec<JSONValue>> {
+        self.skip_leading_comma();
+
         let b = self.current_byte()?;
         let mut vals = Vec::<JSONValue>::new();
 
@@ -311,25 +462,24 @@ impl<'a> ParseContext<'a> {
         Ok(JSONValue::Text(s))
     }
 
-    /// Consumes a f64 Number value
+    /// Consumes a f64 Number value, including the `NaN`/`Infinity`/
+    /// `-Infinity` literals when `allow_nan_and_infinity` is set
     fn number(&mut self) -> Result<JSONValue> {
-        let idx_start = self.index;
-
-        // eat through valid bytes
-        while let Ok(b) = self.current_byte() {
-            match b {
-                b'0'...b'9' | b'-' | b'.' | b'e' | b'E' => self.accept(),
-                _ => break,
+        if self.options.allow_nan_and_infinity {
+            if self.try_eat_literal("-Infinity") {
+                return Ok(JSONValue::Number(f64::NEG_INFINITY));
+            }
+            if self.try_eat_literal("Infinity") {
+                return Ok(JSONValue::Number(f64::INFINITY));
+            }
+            if self.try_eat_literal("NaN") {
+                return Ok(JSONValue::Number(f64::NAN));
             }
         }
 
-        // checked parse
-        let res = lexical_core::try_atof64_slice(&self.bytes[idx_start..self.index]);
-
-        if res.error.code == lexical_core::ErrorCode::Success {
-            Ok(JSONValue::Number(res.value))
-        } else {
-            self.fail()
+        match lex::eat_number(self.bytes, &mut self.index) {
+            Ok(n) => Ok(JSONValue::Number(n)),
+            Err(_) => self.fail(),
         }
     }
 
@@ -342,6 +492,7 @@ impl<'a> ParseContext<'a> {
         // lookahead
         let res = match next {
             b'0'...b'9' | b'-' => self.number(),
+            b'N' | b'I' if self.options.allow_nan_and_infinity => self.number(),
             b't' => {
                 self.eat_str("true")?;
                 Ok(JSONValue::Bool(true))
@@ -362,7 +513,7 @@ impl<'a> ParseContext<'a> {
 
         // commas may trail
         self.skip_control_chars();
-        self.skip_comma();
+        self.skip_comma()?;
 
         res
     }
@@ -377,6 +528,15 @@ mod tests {
     use std::fs::File;
     use std::io::Read;
 
+    fn lenient_ctx(text: &str) -> parse::ParseContext {
+        let options = parse::ParseOptions {
+            allow_trailing_commas: true,
+            ..parse::ParseOptions::default()
+        };
+
+        parse::ParseContext::new_with_options(text, options)
+    }
+
     #[test]
     fn parse_text_and_boolean() {
         let mut obj = HashMap::<&str, JSONValue>::new();
@@ -397,7 +557,7 @@ mod tests {
         obj.insert("myString", "SomeString".into());
 
         let txt = r#"{ "myString": "SomeString", "myBool":  true, }"#;
-        let mut ctx = parse::ParseContext::new(txt);
+        let mut ctx = lenient_ctx(txt);
         let res = ctx.object();
 
         assert_eq!(res.unwrap(), obj.into());
@@ -421,7 +581,7 @@ mod tests {
             },
         }
         "#;
-        let mut ctx = parse::ParseContext::new(txt);
+        let mut ctx = lenient_ctx(txt);
         let res = ctx.object();
 
         assert_eq!(res.unwrap(), obj.into());
@@ -478,7 +638,7 @@ mod tests {
             },
         }
         "#;
-        let mut ctx = parse::ParseContext::new(txt);
+        let mut ctx = lenient_ctx(txt);
         let res = ctx.object();
 
         assert_eq!(res.unwrap(), obj.into());
@@ -501,7 +661,7 @@ mod tests {
 
         "#;
 
-        let mut ctx = parse::ParseContext::new(txt);
+        let mut ctx = lenient_ctx(txt);
         let res = ctx.array();
 
         assert_eq!(res.unwrap(), arr.into());
@@ -527,7 +687,7 @@ mod tests {
             "by\\": "\tthe\\second",
         }"#;
 
-        let mut ctx = parse::ParseContext::new(text);
+        let mut ctx = lenient_ctx(text);
 
         let res = ctx.object();
 
@@ -540,6 +700,148 @@ mod tests {
         assert_eq!(JSONValue::from(map), res.unwrap());
     }
 
+    #[test]
+    fn parse_unicode_escape() {
+        let t1 = "\"caf\\u00e9\"";
+
+        let mut c1 = parse::ParseContext::new(t1);
+
+        let r1 = c1.text();
+
+        assert_eq!(JSONValue::from("café"), r1.unwrap());
+    }
+
+    #[test]
+    fn parse_unicode_surrogate_pair() {
+        // U+1F600 GRINNING FACE, encoded as a UTF-16 surrogate pair
+        let t1 = "\"\\ud83d\\ude00\"";
+
+        let mut c1 = parse::ParseContext::new(t1);
+
+        let r1 = c1.text();
+
+        assert_eq!(JSONValue::from("\u{1F600}"), r1.unwrap());
+    }
+
+    #[test]
+    fn parse_unicode_unpaired_surrogate_fails() {
+        let t1 = r#""\ud83d""#;
+
+        let mut c1 = parse::ParseContext::new(t1);
+
+        assert!(c1.text().is_err());
+    }
+
+    #[test]
+    fn error_reports_line_and_column() {
+        let txt = "{\n  \"a\": tru\n}";
+
+        let mut ctx = parse::ParseContext::new(txt);
+
+        match ctx.parse() {
+            Err(parse::Error::UnexpectedCharacter { line, col, .. }) => {
+                assert_eq!(line, 2);
+                assert_eq!(col, 8);
+            }
+            other => panic!("expected UnexpectedCharacter, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn error_display_is_readable() {
+        let err = parse::Error::UnexpectedCharacter {
+            line: 2,
+            col: 12,
+            token: 'x',
+        };
+
+        assert_eq!(format!("{}", err), "unexpected 'x' at line 2, column 12");
+    }
+
+    #[test]
+    fn strict_rejects_trailing_comma() {
+        let txt = r#"{ "a": true, }"#;
+
+        let mut ctx = parse::ParseContext::new(txt);
+
+        assert!(ctx.object().is_err());
+    }
+
+    #[test]
+    fn leading_comma_requires_option() {
+        let txt = r#"[, 1, 2]"#;
+
+        let mut ctx = parse::ParseContext::new(txt);
+        assert!(ctx.array().is_err());
+
+        let options = parse::ParseOptions {
+            allow_leading_commas: true,
+            ..parse::ParseOptions::default()
+        };
+        let mut ctx = parse::ParseContext::new_with_options(txt, options);
+
+        assert_eq!(ctx.array().unwrap(), vec![1.0.into(), 2.0.into()].into());
+    }
+
+    #[test]
+    fn leading_comma_with_trailing_whitespace_before_close() {
+        let options = parse::ParseOptions {
+            allow_leading_commas: true,
+            ..parse::ParseOptions::default()
+        };
+
+        let mut ctx = parse::ParseContext::new_with_options("[, ]", options);
+        assert_eq!(ctx.array().unwrap(), Vec::<JSONValue>::new().into());
+
+        let mut ctx = parse::ParseContext::new_with_options("{ , }", options);
+        assert_eq!(
+            ctx.object().unwrap(),
+            HashMap::<&str, JSONValue>::new().into()
+        );
+    }
+
+    #[test]
+    fn comments_require_option() {
+        let txt = "{ // a comment\n  \"a\": true /* inline */ }";
+
+        let mut ctx = parse::ParseContext::new(txt);
+        assert!(ctx.object().is_err());
+
+        let options = parse::ParseOptions {
+            allow_comments: true,
+            ..parse::ParseOptions::default()
+        };
+        let mut ctx = parse::ParseContext::new_with_options(txt, options);
+
+        let mut obj = HashMap::<&str, JSONValue>::new();
+        obj.insert("a", true.into());
+
+        assert_eq!(ctx.object().unwrap(), obj.into());
+    }
+
+    #[test]
+    fn nan_and_infinity_require_option() {
+        let txt = "[NaN, Infinity, -Infinity]";
+
+        let mut ctx = parse::ParseContext::new(txt);
+        assert!(ctx.array().is_err());
+
+        let options = parse::ParseOptions {
+            allow_nan_and_infinity: true,
+            ..parse::ParseOptions::default()
+        };
+        let mut ctx = parse::ParseContext::new_with_options(txt, options);
+
+        match ctx.array().unwrap() {
+            JSONValue::Array(vals) => {
+                assert!(matches!(vals[0], JSONValue::Number(n) if n.is_nan()));
+                assert_eq!(vals[1], JSONValue::Number(f64::INFINITY));
+                assert_eq!(vals[2], JSONValue::Number(f64::NEG_INFINITY));
+            }
+            other => panic!("expected array, got {:?}", other),
+        }
+    }
+
     fn file_to_str(path: &'static str) -> String {
         let mut f = File::open(path).unwrap();
 
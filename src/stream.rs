@@ -0,0 +1,404 @@
+use std::str;
+
+use crate::lex;
+
+/// A single token emitted while walking a JSON document depth-first
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonEvent {
+    ObjectStart,
+    ObjectEnd,
+    ArrayStart,
+    ArrayEnd,
+    BooleanValue(bool),
+    NumberValue(f64),
+    StringValue(String),
+    NullValue,
+    Error,
+}
+
+/// One step on the path from the document root to the value an event
+/// describes
+#[derive(Debug, Clone, PartialEq)]
+pub enum StackElement {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ContainerKind {
+    Object,
+    Array,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ContainerState {
+    // nothing has been read from the container yet
+    Start,
+    // at least one value has been read, a comma or closing token is next
+    AfterValue,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Container {
+    kind: ContainerKind,
+    state: ContainerState,
+}
+
+/// Pull-based JSON parser that yields a [`JsonEvent`] per call to `next`
+/// instead of materializing a full `JSONValue` tree, so large documents can
+/// be scanned without allocating the whole structure in memory
+#[derive(Debug)]
+pub struct StreamParser<'a> {
+    bytes: &'a [u8],
+    buffer: Vec<u8>,
+    index: usize,
+    containers: Vec<Container>,
+    stack: Vec<StackElement>,
+    started: bool,
+    done: bool,
+}
+
+impl<'a> StreamParser<'a> {
+    pub fn new(text: &'a str) -> StreamParser<'a> {
+        StreamParser {
+            bytes: text.as_bytes(),
+            buffer: Vec::with_capacity(100),
+            index: 0,
+            containers: Vec::new(),
+            stack: Vec::new(),
+            started: false,
+            done: false,
+        }
+    }
+
+    /// The path from the document root to the value described by the event
+    /// most recently returned from `next`
+    pub fn stack(&self) -> &[StackElement] {
+        &self.stack
+    }
+
+    fn current_byte(&self) -> Option<u8> {
+        self.bytes.get(self.index).copied()
+    }
+
+    fn accept(&mut self) {
+        self.index += 1;
+    }
+
+    fn skip_control_chars(&mut self) {
+        while let Some(byte) = self.current_byte() {
+            match byte {
+                b'\n' | b'\r' | b'\t' | b' ' => self.accept(),
+                _ => break,
+            }
+        }
+    }
+
+    fn eat(&mut self, token: u8) -> Result<(), ()> {
+        if self.current_byte() == Some(token) {
+            self.accept();
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    fn eat_str(&mut self, match_str: &'static str) -> Result<(), ()> {
+        let match_bytes = match_str.as_bytes();
+        let end = self.index + match_bytes.len();
+
+        if end <= self.bytes.len() && &self.bytes[self.index..end] == match_bytes {
+            self.index = end;
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    fn eat_hex4(&mut self) -> Result<u16, ()> {
+        lex::eat_hex4(self.bytes, &mut self.index)
+    }
+
+    fn eat_unicode_escape(&mut self) -> Result<char, ()> {
+        let code_unit = self.eat_hex4()?;
+
+        lex::combine_unicode_escape(self.bytes, &mut self.index, code_unit)
+    }
+
+    fn read_string(&mut self) -> Result<String, ()> {
+        self.eat(b'"')?;
+
+        self.buffer.clear();
+
+        loop {
+            let b = self.current_byte().ok_or(())?;
+
+            match b {
+                b'"' => {
+                    self.accept();
+                    break;
+                }
+                b'\\' => {
+                    self.accept();
+                    let following_b = self.current_byte().ok_or(())?;
+
+                    match following_b {
+                        b'"' => self.buffer.push(b'\"'),
+                        b'\\' => self.buffer.push(b'\\'),
+                        b'/' => self.buffer.push(b'/'),
+                        b'b' => self.buffer.push(0x8),
+                        b'f' => self.buffer.push(0xC),
+                        b'n' => self.buffer.push(b'\n'),
+                        b'r' => self.buffer.push(b'\r'),
+                        b't' => self.buffer.push(b'\t'),
+                        b'u' => {
+                            self.accept();
+                            let ch = self.eat_unicode_escape()?;
+                            let mut utf8_buf = [0u8; 4];
+                            self.buffer
+                                .extend_from_slice(ch.encode_utf8(&mut utf8_buf).as_bytes());
+                            continue;
+                        }
+                        _ => return Err(()),
+                    }
+
+                    self.accept();
+                }
+                _ => {
+                    self.buffer.push(b);
+                    self.accept();
+                }
+            }
+        }
+
+        Ok(unsafe { str::from_utf8_unchecked(&self.buffer[..]).to_owned() })
+    }
+
+    fn read_number(&mut self) -> Result<f64, ()> {
+        lex::eat_number(self.bytes, &mut self.index)
+    }
+
+    /// Reads the value at the current position, emitting the matching leaf
+    /// event or pushing a new container frame for `{`/`[`
+    fn read_value(&mut self) -> JsonEvent {
+        match self.current_byte() {
+            Some(b'{') => {
+                self.accept();
+                self.containers.push(Container {
+                    kind: ContainerKind::Object,
+                    state: ContainerState::Start,
+                });
+                JsonEvent::ObjectStart
+            }
+            Some(b'[') => {
+                self.accept();
+                self.containers.push(Container {
+                    kind: ContainerKind::Array,
+                    state: ContainerState::Start,
+                });
+                JsonEvent::ArrayStart
+            }
+            Some(b'"') => match self.read_string() {
+                Ok(s) => JsonEvent::StringValue(s),
+                Err(_) => JsonEvent::Error,
+            },
+            Some(b't') => match self.eat_str("true") {
+                Ok(_) => JsonEvent::BooleanValue(true),
+                Err(_) => JsonEvent::Error,
+            },
+            Some(b'f') => match self.eat_str("false") {
+                Ok(_) => JsonEvent::BooleanValue(false),
+                Err(_) => JsonEvent::Error,
+            },
+            Some(b'n') => match self.eat_str("null") {
+                Ok(_) => JsonEvent::NullValue,
+                Err(_) => JsonEvent::Error,
+            },
+            Some(b'0'...b'9') | Some(b'-') => match self.read_number() {
+                Ok(n) => JsonEvent::NumberValue(n),
+                Err(_) => JsonEvent::Error,
+            },
+            _ => JsonEvent::Error,
+        }
+    }
+
+    fn error(&mut self) -> Option<JsonEvent> {
+        self.done = true;
+        Some(JsonEvent::Error)
+    }
+
+    fn next_event(&mut self) -> Option<JsonEvent> {
+        if self.done {
+            return None;
+        }
+
+        self.skip_control_chars();
+
+        if self.containers.is_empty() {
+            if self.started {
+                self.done = true;
+                return None;
+            }
+
+            self.started = true;
+
+            return match self.current_byte() {
+                Some(b'{') | Some(b'[') => Some(self.read_value()),
+                _ => self.error(),
+            };
+        }
+
+        let top_idx = self.containers.len() - 1;
+        let kind = self.containers[top_idx].kind;
+        let state = self.containers[top_idx].state;
+
+        match (kind, state) {
+            (ContainerKind::Object, ContainerState::Start) => {
+                match self.current_byte() {
+                    Some(b'}') => {
+                        self.accept();
+                        self.end_container(JsonEvent::ObjectEnd)
+                    }
+                    Some(b'"') => self.read_object_entry(top_idx),
+                    _ => self.error(),
+                }
+            }
+            (ContainerKind::Object, ContainerState::AfterValue) => {
+                match self.current_byte() {
+                    Some(b'}') => {
+                        self.stack.pop();
+                        self.accept();
+                        self.end_container(JsonEvent::ObjectEnd)
+                    }
+                    Some(b',') => {
+                        self.accept();
+                        self.skip_control_chars();
+                        self.stack.pop();
+
+                        match self.current_byte() {
+                            Some(b'"') => self.read_object_entry(top_idx),
+                            _ => self.error(),
+                        }
+                    }
+                    _ => self.error(),
+                }
+            }
+            (ContainerKind::Array, ContainerState::Start) => match self.current_byte() {
+                Some(b']') => {
+                    self.accept();
+                    self.end_container(JsonEvent::ArrayEnd)
+                }
+                Some(_) => {
+                    self.stack.push(StackElement::Index(0));
+                    self.containers[top_idx].state = ContainerState::AfterValue;
+                    Some(self.read_value())
+                }
+                None => self.error(),
+            },
+            (ContainerKind::Array, ContainerState::AfterValue) => match self.current_byte() {
+                Some(b']') => {
+                    self.stack.pop();
+                    self.accept();
+                    self.end_container(JsonEvent::ArrayEnd)
+                }
+                Some(b',') => {
+                    self.accept();
+                    self.skip_control_chars();
+
+                    let next_index = match self.stack.pop() {
+                        Some(StackElement::Index(i)) => i + 1,
+                        _ => return self.error(),
+                    };
+                    self.stack.push(StackElement::Index(next_index));
+
+                    Some(self.read_value())
+                }
+                _ => self.error(),
+            },
+        }
+    }
+
+    fn read_object_entry(&mut self, container_idx: usize) -> Option<JsonEvent> {
+        let key = match self.read_string() {
+            Ok(k) => k,
+            Err(_) => return self.error(),
+        };
+
+        self.skip_control_chars();
+
+        if self.eat(b':').is_err() {
+            return self.error();
+        }
+
+        self.skip_control_chars();
+
+        self.stack.push(StackElement::Key(key));
+        self.containers[container_idx].state = ContainerState::AfterValue;
+
+        Some(self.read_value())
+    }
+
+    fn end_container(&mut self, event: JsonEvent) -> Option<JsonEvent> {
+        self.containers.pop();
+        self.skip_control_chars();
+
+        if let Some(parent) = self.containers.last_mut() {
+            parent.state = ContainerState::AfterValue;
+        } else {
+            self.done = true;
+        }
+
+        Some(event)
+    }
+}
+
+impl<'a> Iterator for StreamParser<'a> {
+    type Item = JsonEvent;
+
+    fn next(&mut self) -> Option<JsonEvent> {
+        let event = self.next_event();
+
+        self.skip_control_chars();
+
+        event
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_flat_object() {
+        let events: Vec<JsonEvent> =
+            StreamParser::new(r#"{"a": 1, "b": true}"#).collect();
+
+        assert_eq!(
+            events,
+            vec![
+                JsonEvent::ObjectStart,
+                JsonEvent::NumberValue(1.0),
+                JsonEvent::BooleanValue(true),
+                JsonEvent::ObjectEnd,
+            ]
+        );
+    }
+
+    #[test]
+    fn stream_nested_array_tracks_stack() {
+        let mut parser = StreamParser::new(r#"{"items": [1, 2]}"#);
+
+        assert_eq!(parser.next(), Some(JsonEvent::ObjectStart));
+        assert_eq!(parser.next(), Some(JsonEvent::ArrayStart));
+        assert_eq!(parser.stack(), &[StackElement::Key("items".to_owned())]);
+        assert_eq!(parser.next(), Some(JsonEvent::NumberValue(1.0)));
+        assert_eq!(
+            parser.stack(),
+            &[StackElement::Key("items".to_owned()), StackElement::Index(0)]
+        );
+        assert_eq!(parser.next(), Some(JsonEvent::NumberValue(2.0)));
+        assert_eq!(parser.next(), Some(JsonEvent::ArrayEnd));
+        assert_eq!(parser.next(), Some(JsonEvent::ObjectEnd));
+        assert_eq!(parser.next(), None);
+    }
+}